@@ -1,34 +1,279 @@
 #[cfg(test)]
 mod tests;
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub struct SnowflakeGenerator {
     epoch: SystemTime,
     last_time: u64,
+    node_id: u64,
+    counter: u64,
+    layout: SnowflakeLayout,
+}
+
+/// A thread-safe `SnowflakeGenerator`.
+///
+/// Unlike [`SnowflakeGenerator`], [`generate`](SharedSnowflakeGenerator::generate)
+/// only needs `&self`, so a single instance can be wrapped in an `Arc` and
+/// cloned across threads. The last-used timestamp and counter are packed
+/// into a single `AtomicU64` and updated with a compare-and-swap loop, so the
+/// common (uncontended, or merely racing) case never has to block on a lock.
+pub struct SharedSnowflakeGenerator {
+    epoch: SystemTime,
     machine_id: u8,
     thread_id: u8,
-    counter: u16,
+    state: AtomicU64,
+}
+
+/// The bit-width layout used to pack a timestamp, node ID, and sequence
+/// counter into a single `u64` Snowflake ID, counted from the most
+/// significant bit down: timestamp, then node, then sequence.
+///
+/// The three widths must sum to 63 or 64 -- 64 uses every bit of the `u64`,
+/// while 63 matches Twitter's original layout, which reserves the top bit so
+/// the ID fits in a signed 64-bit integer. This crate's default layout is
+/// 42/10/12, which uses the full 64 bits.
+///
+/// The layout also carries a `time_unit_millis`, the resolution the
+/// timestamp is measured in. It defaults to 1 (millisecond resolution); a
+/// coarser unit (e.g. 10ms, Sonyflake-style) stretches the representable
+/// time range at the cost of precision, which lets a layout trade timestamp
+/// bits for more node or sequence bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnowflakeLayout {
+    time_bits: u8,
+    node_bits: u8,
+    sequence_bits: u8,
+    time_unit_millis: u64,
+}
+
+impl SnowflakeLayout {
+    /// This crate's default layout: 42 bits of millisecond timestamp, 10 bits
+    /// of node ID, and 12 bits of per-millisecond sequence counter.
+    pub const DEFAULT: SnowflakeLayout = SnowflakeLayout {
+        time_bits: 42,
+        node_bits: 10,
+        sequence_bits: 12,
+        time_unit_millis: 1,
+    };
+
+    /// Creates a new layout with the given bit widths and millisecond
+    /// resolution.
+    ///
+    /// # Panics
+    /// Panics if `time_bits`, `node_bits`, or `sequence_bits` is greater than
+    /// 63, or if `time_bits + node_bits + sequence_bits` is not 63 or 64.
+    pub fn new(time_bits: u8, node_bits: u8, sequence_bits: u8) -> Self {
+        SnowflakeLayout::with_time_unit_millis(time_bits, node_bits, sequence_bits, 1)
+    }
+
+    /// Creates a new layout with the given bit widths, measuring the
+    /// timestamp in `time_unit_millis`-millisecond increments instead of
+    /// individual milliseconds.
+    ///
+    /// # Panics
+    /// Panics if `time_bits`, `node_bits`, or `sequence_bits` is greater than
+    /// 63, if `time_bits + node_bits + sequence_bits` is not 63 or 64, or if
+    /// `time_unit_millis` is 0.
+    pub fn with_time_unit_millis(
+        time_bits: u8,
+        node_bits: u8,
+        sequence_bits: u8,
+        time_unit_millis: u64,
+    ) -> Self {
+        assert!(
+            time_bits <= 63 && node_bits <= 63 && sequence_bits <= 63,
+            "time_bits, node_bits, and sequence_bits must each be 63 or fewer, got ({time_bits}, {node_bits}, {sequence_bits})"
+        );
+        let total = time_bits as u16 + node_bits as u16 + sequence_bits as u16;
+        assert!(
+            total == 63 || total == 64,
+            "time_bits + node_bits + sequence_bits must sum to 63 or 64, got {total}"
+        );
+        assert!(time_unit_millis > 0, "time_unit_millis must be at least 1");
+        SnowflakeLayout {
+            time_bits,
+            node_bits,
+            sequence_bits,
+            time_unit_millis,
+        }
+    }
+
+    /// How many bits are reserved for the timestamp.
+    pub fn time_bits(&self) -> u8 {
+        self.time_bits
+    }
+
+    /// How many bits are reserved for the node ID.
+    pub fn node_bits(&self) -> u8 {
+        self.node_bits
+    }
+
+    /// How many bits are reserved for the sequence counter.
+    pub fn sequence_bits(&self) -> u8 {
+        self.sequence_bits
+    }
+
+    /// How many milliseconds one increment of the encoded timestamp spans.
+    pub fn time_unit_millis(&self) -> u64 {
+        self.time_unit_millis
+    }
+
+    /// The one-past-the-end value for the sequence counter (`2^sequence_bits`).
+    pub fn max_sequence(&self) -> u64 {
+        1u64 << self.sequence_bits
+    }
+
+    /// The one-past-the-end value for the node ID (`2^node_bits`).
+    pub fn max_node(&self) -> u64 {
+        1u64 << self.node_bits
+    }
+
+    fn node_shift(&self) -> u32 {
+        self.sequence_bits as u32
+    }
+
+    fn time_shift(&self) -> u32 {
+        self.node_bits as u32 + self.sequence_bits as u32
+    }
+
+    fn node_mask(&self) -> u64 {
+        (self.max_node() - 1) << self.node_shift()
+    }
+
+    fn sequence_mask(&self) -> u64 {
+        self.max_sequence() - 1
+    }
+}
+
+impl Default for SnowflakeLayout {
+    fn default() -> Self {
+        SnowflakeLayout::DEFAULT
+    }
+}
+
+/// Builds a [`SnowflakeGenerator`] with a custom bit-width layout.
+///
+/// # Examples
+/// ```
+/// # use snowflake::SnowflakeGenerator;
+/// let generator = SnowflakeGenerator::builder()
+///     .time_bits(41)
+///     .node_bits(10)
+///     .sequence_bits(12)
+///     .build(5);
+/// ```
+pub struct SnowflakeGeneratorBuilder {
+    epoch: SystemTime,
+    time_bits: u8,
+    node_bits: u8,
+    sequence_bits: u8,
+    time_unit_millis: u64,
+}
+
+impl SnowflakeGeneratorBuilder {
+    fn new() -> Self {
+        SnowflakeGeneratorBuilder {
+            epoch: UNIX_EPOCH,
+            time_bits: SnowflakeLayout::DEFAULT.time_bits,
+            node_bits: SnowflakeLayout::DEFAULT.node_bits,
+            sequence_bits: SnowflakeLayout::DEFAULT.sequence_bits,
+            time_unit_millis: SnowflakeLayout::DEFAULT.time_unit_millis,
+        }
+    }
+
+    /// Sets the epoch the generator will measure time from. Defaults to
+    /// `UNIX_EPOCH`. See [`SnowflakeGenerator::with_epoch`].
+    pub fn epoch(mut self, epoch: SystemTime) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// Sets how many bits of the ID are reserved for the timestamp.
+    pub fn time_bits(mut self, time_bits: u8) -> Self {
+        self.time_bits = time_bits;
+        self
+    }
+
+    /// Sets how many bits of the ID are reserved for the node ID.
+    pub fn node_bits(mut self, node_bits: u8) -> Self {
+        self.node_bits = node_bits;
+        self
+    }
+
+    /// Sets how many bits of the ID are reserved for the sequence counter.
+    pub fn sequence_bits(mut self, sequence_bits: u8) -> Self {
+        self.sequence_bits = sequence_bits;
+        self
+    }
+
+    /// Sets the timestamp's resolution in milliseconds. Defaults to 1
+    /// (millisecond resolution). A coarser unit, e.g. 10ms as Sonyflake
+    /// uses, stretches the representable time range roughly `time_unit_millis`x
+    /// at the cost of that much timestamp precision -- useful when trading
+    /// timestamp bits for more node or sequence bits.
+    pub fn time_unit_millis(mut self, time_unit_millis: u64) -> Self {
+        self.time_unit_millis = time_unit_millis;
+        self
+    }
+
+    /// Builds the generator with the given node ID.
+    ///
+    /// # Panics
+    /// Panics if `time_bits + node_bits + sequence_bits` is not 63 or 64, if
+    /// `time_unit_millis` is 0, or if `node_id` does not fit in `node_bits`.
+    pub fn build(self, node_id: u64) -> SnowflakeGenerator {
+        let layout = SnowflakeLayout::with_time_unit_millis(
+            self.time_bits,
+            self.node_bits,
+            self.sequence_bits,
+            self.time_unit_millis,
+        );
+        assert!(
+            node_id < layout.max_node(),
+            "node_id must fit in node_bits"
+        );
+        SnowflakeGenerator {
+            epoch: self.epoch,
+            last_time: get_time_units(self.epoch, layout.time_unit_millis),
+            node_id,
+            counter: 0,
+            layout,
+        }
+    }
+
+    /// Builds the generator with a node ID derived from host identity
+    /// instead of an explicit one. See [`SnowflakeGenerator::with_auto_node`].
+    pub fn build_with_auto_node(self) -> SnowflakeGenerator {
+        let node_id = derive_node_id(self.node_bits);
+        self.build(node_id)
+    }
 }
 
 #[derive(Debug)]
 pub struct Snowflake {
-    /// The time in milliseconds since the epoch.
+    /// The time in milliseconds since the epoch, already scaled back up by
+    /// the layout's `time_unit_millis` if it used a coarser-than-millisecond
+    /// resolution.
     /// This field does not automatically compensate if an epoc other than UNIX_EPOCH is used.
     pub time: u64,
-    /// The machine ID the snowflake was generated on.
-    pub machine_id: u8,
-    /// The thread ID the snowflake was generated on.
-    pub thread_id: u8,
+    /// The node ID the snowflake was generated on.
+    pub node_id: u64,
     /// The counter for the snowflake. This is incremented every time a snowflake is generated
     /// and reset if the time has changed
-    pub counter: u16,
+    pub counter: u64,
 }
 
 impl SnowflakeGenerator {
     /// Creates a new SnowflakeGenerator with the given machine ID and thread ID.
     /// The machine ID must be less than 32 and the thread ID must be less than 32.
     ///
+    /// This uses [`SnowflakeLayout::DEFAULT`] (42/10/12 bits), packing
+    /// `machine_id` and `thread_id` into the 10-bit node ID as `machine_id <<
+    /// 5 | thread_id`. See [`SnowflakeGenerator::builder`] to pick a
+    /// different bit-width layout.
+    ///
     /// # Examples
     /// ```
     /// # use snowflake::SnowflakeGenerator;
@@ -68,18 +313,86 @@ impl SnowflakeGenerator {
     pub fn with_epoch(epoch: SystemTime, machine_id: u8, thread_id: u8) -> Self {
         assert!(machine_id < 32, "machine_id must be less than 32");
         assert!(thread_id < 32, "thread_id must be less than 32");
+        let node_id = ((machine_id as u64) << 5) | thread_id as u64;
         SnowflakeGenerator {
             epoch,
             last_time: get_time_millis(epoch),
-            machine_id,
-            thread_id,
+            node_id,
             counter: 0,
+            layout: SnowflakeLayout::DEFAULT,
         }
     }
 
+    /// Starts building a `SnowflakeGenerator` with a custom bit-width layout,
+    /// e.g. Twitter's original 41/10/12 split, or a layout with more node or
+    /// sequence bits than the default. See [`SnowflakeGeneratorBuilder`].
+    pub fn builder() -> SnowflakeGeneratorBuilder {
+        SnowflakeGeneratorBuilder::new()
+    }
+
+    /// Decodes a Snowflake ID generated by this generator, using its layout.
+    ///
+    /// This is a convenience over [`Snowflake::decode_with_layout`] for the
+    /// common case where the generator that produced the ID is still around;
+    /// it already knows which bit-width layout to use. The returned
+    /// Snowflake's `time` is still relative to the generator's epoch: pass
+    /// [`SnowflakeGenerator::epoch`] to [`Snowflake::timestamp`] to get the
+    /// absolute time.
+    ///
+    /// # Examples
+    /// ```
+    /// # use snowflake::SnowflakeGenerator;
+    /// let mut generator = SnowflakeGenerator::new(0, 0);
+    /// let id = generator.generate();
+    /// let snowflake = generator.decode(id);
+    /// let created_at = snowflake.timestamp(generator.epoch());
+    /// ```
+    pub fn decode(&self, id: u64) -> Snowflake {
+        Snowflake::decode_with_layout(id, &self.layout)
+    }
+
+    /// The epoch this generator measures time from.
+    pub fn epoch(&self) -> SystemTime {
+        self.epoch
+    }
+
+    /// Creates a generator whose node ID is derived from this host's
+    /// identity instead of being assigned manually.
+    ///
+    /// This hashes a stable host identifier (the OS-reported hostname,
+    /// preferring `/proc/sys/kernel/hostname` or the `gethostname` syscall over
+    /// the `HOSTNAME`/`COMPUTERNAME` environment variables, which aren't
+    /// always set) down into [`SnowflakeLayout::DEFAULT`]'s 10-bit node ID,
+    /// so a fleet of instances
+    /// spun up without centrally assigned IDs is unlikely to collide. If no
+    /// host identifier is available, a fixed fallback string is hashed
+    /// instead so the generator still constructs successfully -- operators
+    /// relying on the fallback should assign node IDs manually to avoid
+    /// collisions between hosts. Use [`SnowflakeGenerator::node_id`] to log
+    /// or verify the value this chose.
+    pub fn with_auto_node() -> Self {
+        SnowflakeGenerator::builder().build_with_auto_node()
+    }
+
+    /// The node ID this generator is using.
+    ///
+    /// Mostly useful to log/verify the value chosen by
+    /// [`SnowflakeGenerator::with_auto_node`].
+    pub fn node_id(&self) -> u64 {
+        self.node_id
+    }
+
     /// Generates a new Snowflake ID.
     /// This function will block until it can generate a new ID.
     ///
+    /// # Monotonicity
+    /// If the system clock steps backward, this rebases on `last_time` (the
+    /// high-water mark of the last timestamp used) instead of the smaller
+    /// observed time, so returned IDs stay strictly increasing; the sequence
+    /// keeps advancing within that stale millisecond until real time catches
+    /// back up. Use [`SnowflakeGenerator::try_generate`] instead if you'd
+    /// rather be told about a rollback than have it silently smoothed over.
+    ///
     /// # Examples
     /// ```
     /// # use snowflake::SnowflakeGenerator;
@@ -87,17 +400,22 @@ impl SnowflakeGenerator {
     /// let id = generator.generate();
     /// ```
     pub fn generate(&mut self) -> u64 {
-        let mut now = get_time_millis(self.epoch);
+        let mut now = get_time_units(self.epoch, self.layout.time_unit_millis);
+        let max_counter = self.layout.max_sequence();
 
-        // If the time is the same as the last time we generated an ID, we need to increment our counter
-        if now == self.last_time {
-            self.counter = (self.counter + 1) % 4096;
+        // Either this is the same millisecond as last time, or the clock
+        // stepped backward; either way we rebase on `last_time` and need to
+        // increment our counter.
+        if now <= self.last_time {
+            self.counter = (self.counter + 1) % max_counter;
             if self.counter == 0 {
                 // If we've reached the maximum number of IDs we can generate in a single millisecond,
                 // we need to wait until the next millisecond
                 while now <= self.last_time {
-                    now = get_time_millis(self.epoch);
+                    now = get_time_units(self.epoch, self.layout.time_unit_millis);
                 }
+            } else {
+                now = self.last_time;
             }
         } else {
             // This is a new millisecond so we reset our counter
@@ -106,15 +424,67 @@ impl SnowflakeGenerator {
 
         self.last_time = now;
 
-        self.last_time << 22
-            | ((self.machine_id as u64) << 17)
-            | ((self.thread_id as u64) << 12)
-            | (self.counter as u64)
+        self.last_time << self.layout.time_shift()
+            | (self.node_id << self.layout.node_shift())
+            | self.counter
+    }
+
+    /// Generates a new Snowflake ID, or reports how far backward the clock moved.
+    ///
+    /// # Monotonicity
+    /// Returns `Err(ClockError)` as soon as the system clock is observed
+    /// behind `last_time` (the last timestamp this generator used), rather
+    /// than fabricating a timestamp the way [`SnowflakeGenerator::generate`]
+    /// does. Use this when a caller needs to detect and react to clock
+    /// rollbacks instead of having them silently rebased.
+    ///
+    /// # Examples
+    /// ```
+    /// # use snowflake::SnowflakeGenerator;
+    /// let mut generator = SnowflakeGenerator::new(0, 0);
+    /// let id = generator.try_generate().unwrap();
+    /// ```
+    pub fn try_generate(&mut self) -> Result<u64, ClockError> {
+        let mut now = get_time_units(self.epoch, self.layout.time_unit_millis);
+        if now < self.last_time {
+            return Err(ClockError {
+                drift_millis: (self.last_time - now) * self.layout.time_unit_millis,
+            });
+        }
+        let max_counter = self.layout.max_sequence();
+
+        if now == self.last_time {
+            self.counter = (self.counter + 1) % max_counter;
+            if self.counter == 0 {
+                while now <= self.last_time {
+                    now = get_time_units(self.epoch, self.layout.time_unit_millis);
+                    if now < self.last_time {
+                        return Err(ClockError {
+                            drift_millis: (self.last_time - now) * self.layout.time_unit_millis,
+                        });
+                    }
+                }
+            }
+        } else {
+            self.counter = 0;
+        }
+
+        self.last_time = now;
+
+        Ok(self.last_time << self.layout.time_shift()
+            | (self.node_id << self.layout.node_shift())
+            | self.counter)
     }
 
     /// Generates a new Snowflake ID.
     /// This function will not block and will increment the timestamp if the counter is full.
     ///
+    /// # Monotonicity
+    /// Like [`SnowflakeGenerator::generate`], this rebases on `last_time` if
+    /// the system clock steps backward, so the returned IDs never decrease --
+    /// it just does so without blocking, by advancing the stale millisecond
+    /// by one instead of waiting for real time to catch up.
+    ///
     /// # Examples
     /// ```
     /// # use snowflake::SnowflakeGenerator;
@@ -122,16 +492,19 @@ impl SnowflakeGenerator {
     /// let id = generator.generate_fuzzy();
     /// ```
     pub fn generate_fuzzy(&mut self) -> u64 {
-        let mut now = get_time_millis(self.epoch);
+        let mut now = get_time_units(self.epoch, self.layout.time_unit_millis);
+        let max_counter = self.layout.max_sequence();
 
         // If the actual time is less than or the same as the last time we generated an ID,
         // we need to increment our counter
         if now <= self.last_time {
-            self.counter = (self.counter + 1) % 4096;
+            self.counter = (self.counter + 1) % max_counter;
             if self.counter == 0 {
                 // If we've reached the maximum number of IDs we can generate in a single millisecond,
                 // we need to increment the current millisecond
-                now += 1;
+                now = self.last_time + 1;
+            } else {
+                now = self.last_time;
             }
         } else {
             // This is a new millisecond so we reset our counter
@@ -140,35 +513,246 @@ impl SnowflakeGenerator {
 
         self.last_time = now;
 
-        self.last_time << 22
-            | ((self.machine_id as u64) << 17)
-            | ((self.thread_id as u64) << 12)
-            | (self.counter as u64)
+        self.last_time << self.layout.time_shift()
+            | (self.node_id << self.layout.node_shift())
+            | self.counter
     }
 }
 
-impl From<u64> for Snowflake {
-    fn from(value: u64) -> Self {
+/// Error returned by [`SnowflakeGenerator::try_generate`] when the system
+/// clock has stepped backward since the last ID this generator produced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockError {
+    /// How many milliseconds backward the clock moved.
+    pub drift_millis: u64,
+}
+
+impl std::fmt::Display for ClockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "system clock moved backwards by {} ms", self.drift_millis)
+    }
+}
+
+impl std::error::Error for ClockError {}
+
+impl SharedSnowflakeGenerator {
+    /// Creates a new SharedSnowflakeGenerator with the given machine ID and thread ID.
+    /// The machine ID must be less than 32 and the thread ID must be less than 32.
+    ///
+    /// # Examples
+    /// ```
+    /// # use snowflake::SharedSnowflakeGenerator;
+    /// # use std::sync::Arc;
+    /// let generator = Arc::new(SharedSnowflakeGenerator::new(0, 0));
+    /// ```
+    /// # Panics
+    /// This function will panic if the machine ID or thread ID is greater than 31.
+    pub fn new(machine_id: u8, thread_id: u8) -> Self {
+        SharedSnowflakeGenerator::with_epoch(UNIX_EPOCH, machine_id, thread_id)
+    }
+
+    /// Creates a new SharedSnowflakeGenerator with the given epoch, machine ID, and thread ID.
+    /// See [`SnowflakeGenerator::with_epoch`] for details on the epoch parameter.
+    ///
+    /// # Panics
+    /// This function will panic if the machine ID or thread ID is greater than 31.
+    pub fn with_epoch(epoch: SystemTime, machine_id: u8, thread_id: u8) -> Self {
+        assert!(machine_id < 32, "machine_id must be less than 32");
+        assert!(thread_id < 32, "thread_id must be less than 32");
+        SharedSnowflakeGenerator {
+            epoch,
+            machine_id,
+            thread_id,
+            state: AtomicU64::new(pack_state(get_time_millis(epoch), 0)),
+        }
+    }
+
+    /// Generates a new Snowflake ID.
+    ///
+    /// Safe to call concurrently from many threads sharing this generator
+    /// (e.g. via `Arc<SharedSnowflakeGenerator>`); callers still observe
+    /// unique, monotonically non-decreasing IDs the same way a single
+    /// thread driving a [`SnowflakeGenerator`] would.
+    ///
+    /// # Examples
+    /// ```
+    /// # use snowflake::SharedSnowflakeGenerator;
+    /// let generator = SharedSnowflakeGenerator::new(0, 0);
+    /// let id = generator.generate();
+    /// ```
+    pub fn generate(&self) -> u64 {
+        let layout = SnowflakeLayout::DEFAULT;
+        loop {
+            let now = get_time_millis(self.epoch);
+            let current = self.state.load(Ordering::Relaxed);
+            let (last_time, counter) = unpack_state(current);
+
+            let (new_time, new_counter) = if now <= last_time {
+                let next_counter = (counter + 1) % layout.max_sequence();
+                if next_counter == 0 {
+                    // Out of sequence numbers for this millisecond; spin until
+                    // time (or a racing thread) advances before retrying.
+                    continue;
+                }
+                (last_time, next_counter)
+            } else {
+                (now, 0)
+            };
+
+            let new_state = pack_state(new_time, new_counter);
+            if self
+                .state
+                .compare_exchange_weak(current, new_state, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                let node_id = ((self.machine_id as u64) << 5) | self.thread_id as u64;
+                return new_time << layout.time_shift()
+                    | (node_id << layout.node_shift())
+                    | new_counter;
+            }
+        }
+    }
+}
+
+impl Snowflake {
+    /// Decodes a Snowflake ID using a custom bit-width layout.
+    ///
+    /// Use this instead of [`From<u64>`](Snowflake#impl-From<u64>-for-Snowflake)
+    /// when the ID was generated by a [`SnowflakeGenerator`] built via
+    /// [`SnowflakeGenerator::builder`] with a non-default layout.
+    pub fn decode_with_layout(value: u64, layout: &SnowflakeLayout) -> Self {
         Snowflake {
-            time: value >> 22,
-            machine_id: ((value & 0x3E0000) >> 17) as u8,
-            thread_id: ((value & 0x1F000) >> 12) as u8,
-            counter: (value & 0xFFF) as u16,
+            time: (value >> layout.time_shift()) * layout.time_unit_millis,
+            node_id: (value & layout.node_mask()) >> layout.node_shift(),
+            counter: value & layout.sequence_mask(),
         }
     }
+
+    /// Re-encodes this Snowflake into a `u64` using a custom bit-width layout.
+    pub fn encode_with_layout(&self, layout: &SnowflakeLayout) -> u64 {
+        (self.time / layout.time_unit_millis) << layout.time_shift()
+            | (self.node_id << layout.node_shift())
+            | (self.counter & layout.sequence_mask())
+    }
+
+    /// Recovers the absolute wall-clock time this Snowflake was generated at.
+    ///
+    /// `time` alone is only milliseconds since `epoch`, so it's meaningless
+    /// without knowing which epoch the generator used; this adds it back.
+    /// When you still have the generator that produced the ID, decode with
+    /// [`SnowflakeGenerator::decode`] and pass [`SnowflakeGenerator::epoch`]
+    /// here.
+    ///
+    /// # Examples
+    /// ```
+    /// # use snowflake::{Snowflake, SnowflakeGenerator};
+    /// # use std::time::UNIX_EPOCH;
+    /// let mut generator = SnowflakeGenerator::new(0, 0);
+    /// let id = generator.generate();
+    /// let snowflake = Snowflake::from(id);
+    /// let created_at = snowflake.timestamp(UNIX_EPOCH);
+    /// ```
+    pub fn timestamp(&self, epoch: SystemTime) -> SystemTime {
+        epoch + std::time::Duration::from_millis(self.time)
+    }
+}
+
+impl From<u64> for Snowflake {
+    fn from(value: u64) -> Self {
+        Snowflake::decode_with_layout(value, &SnowflakeLayout::DEFAULT)
+    }
 }
 impl From<Snowflake> for u64 {
     fn from(value: Snowflake) -> Self {
-        value.time << 22
-            | ((value.machine_id as u64) << 17)
-            | ((value.thread_id as u64) << 12)
-            | (value.counter as u64)
+        value.encode_with_layout(&SnowflakeLayout::DEFAULT)
     }
 }
 
+/// Milliseconds elapsed since `epoch`, saturating at 0 if `epoch` is in the
+/// future (e.g. a generator built with an epoch that hasn't arrived yet),
+/// rather than panicking. This keeps [`SnowflakeGenerator::try_generate`]
+/// from panicking on a future epoch: `now` just stays pinned at 0 until the
+/// epoch arrives, and is treated like repeated calls within one time unit.
 fn get_time_millis(epoch: SystemTime) -> u64 {
     SystemTime::now()
         .duration_since(epoch)
-        .expect("time is before epoch")
-        .as_millis() as u64
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Milliseconds elapsed since `epoch`, expressed in `time_unit_millis`-sized
+/// units rather than individual milliseconds.
+fn get_time_units(epoch: SystemTime, time_unit_millis: u64) -> u64 {
+    get_time_millis(epoch) / time_unit_millis
+}
+
+/// A stable identifier for this host, used to derive a node ID.
+///
+/// Prefers the OS-reported hostname -- `/proc/sys/kernel/hostname` on Linux,
+/// or the `gethostname` syscall on other Unix targets -- since the
+/// `HOSTNAME`/`COMPUTERNAME` environment variables are only populated in some
+/// environments (notably Docker/Kubernetes); most plain Linux processes,
+/// systemd units, and cron jobs never see them set. Deliberately avoids
+/// spawning a `hostname` subprocess: a library forking/exec-ing a
+/// PATH-resolved binary just to read the hostname is both a supply-chain
+/// foothold and a source of needless latency. Only falls back to those env
+/// vars, and finally to a fixed string, if no OS-level hostname could be
+/// read, so node ID derivation always succeeds -- at the cost of that
+/// last-resort fallback no longer being unique across hosts.
+fn host_identifier() -> String {
+    #[cfg(target_os = "linux")]
+    if let Ok(name) = std::fs::read_to_string("/proc/sys/kernel/hostname") {
+        let name = name.trim();
+        if !name.is_empty() {
+            return name.to_string();
+        }
+    }
+
+    #[cfg(unix)]
+    if let Some(name) = unix_gethostname() {
+        if !name.is_empty() {
+            return name;
+        }
+    }
+
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .unwrap_or_else(|_| "unknown-snowflake-host".to_string())
+}
+
+/// Reads the hostname via the `gethostname(2)` syscall, with no subprocess
+/// involved.
+#[cfg(unix)]
+fn unix_gethostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    let ret = unsafe { gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if ret != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    std::str::from_utf8(&buf[..len]).ok().map(String::from)
+}
+
+#[cfg(unix)]
+extern "C" {
+    fn gethostname(name: *mut std::ffi::c_char, len: usize) -> i32;
+}
+
+/// Hashes [`host_identifier`] down into a value that fits in `node_bits`.
+fn derive_node_id(node_bits: u8) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    host_identifier().hash(&mut hasher);
+    hasher.finish() % (1u64 << node_bits)
+}
+
+/// Packs a `(last_time, counter)` pair into a single `u64` so it can live in
+/// an `AtomicU64`. `last_time` only ever needs 42 bits in practice, so 12
+/// bits are reserved below it for the counter, leaving plenty of headroom.
+fn pack_state(last_time: u64, counter: u64) -> u64 {
+    (last_time << 12) | counter
+}
+
+fn unpack_state(state: u64) -> (u64, u64) {
+    (state >> 12, state & 0xFFF)
 }
@@ -1,4 +1,7 @@
 use super::*;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 #[test]
 #[should_panic]
@@ -52,3 +55,213 @@ fn test_conversion() {
     let snowflake = Snowflake::from(id);
     assert_eq!(id, u64::from(snowflake));
 }
+
+#[test]
+fn test_shared_unique() {
+    let generator = SharedSnowflakeGenerator::new(0, 0);
+    assert_ne!(generator.generate(), generator.generate());
+}
+
+#[test]
+fn test_shared_unique_generator() {
+    let generator1 = SharedSnowflakeGenerator::new(0, 0);
+    let generator2 = SharedSnowflakeGenerator::new(0, 1);
+    assert_ne!(generator1.generate(), generator2.generate());
+}
+
+#[test]
+fn test_shared_many_unique_concurrent() {
+    let generator = Arc::new(SharedSnowflakeGenerator::new(0, 0));
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let generator = Arc::clone(&generator);
+            thread::spawn(move || (0..1_000).map(|_| generator.generate()).collect::<Vec<_>>())
+        })
+        .collect();
+
+    let mut ids: Vec<u64> = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect();
+    ids.sort();
+    for i in 0..ids.len() - 1 {
+        assert_ne!(ids[i], ids[i + 1]);
+    }
+}
+
+#[test]
+fn test_custom_layout_round_trip() {
+    let layout = SnowflakeLayout::new(41, 10, 12);
+    let mut generator = SnowflakeGenerator::builder()
+        .time_bits(41)
+        .node_bits(10)
+        .sequence_bits(12)
+        .build(5);
+    let id = generator.generate();
+    let snowflake = Snowflake::decode_with_layout(id, &layout);
+    assert_eq!(snowflake.node_id, 5);
+    assert_eq!(id, snowflake.encode_with_layout(&layout));
+}
+
+#[test]
+fn test_custom_layout_more_sequence_bits() {
+    let mut generator = SnowflakeGenerator::builder()
+        .time_bits(38)
+        .node_bits(10)
+        .sequence_bits(16)
+        .build(1);
+    let mut ids = Vec::new();
+    for _ in 0..20_000 {
+        ids.push(generator.generate());
+    }
+    ids.sort();
+    for i in 0..ids.len() - 1 {
+        assert_ne!(ids[i], ids[i + 1]);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_layout_bit_widths_must_sum_to_63_or_64() {
+    SnowflakeLayout::new(40, 10, 12);
+}
+
+#[test]
+#[should_panic]
+fn test_layout_single_field_cannot_claim_all_bits() {
+    // Sums to 64, so the total check alone would let this through, but a
+    // lone 64-bit field would overflow the `1u64 << bits` shift used to
+    // compute `max_node`/`max_sequence`.
+    SnowflakeLayout::new(0, 64, 0);
+}
+
+#[test]
+#[should_panic]
+fn test_builder_node_id_must_fit_node_bits() {
+    SnowflakeGenerator::builder().node_bits(2).build(4);
+}
+
+#[test]
+fn test_try_generate_ok_when_clock_is_sane() {
+    let mut generator = SnowflakeGenerator::new(0, 0);
+    assert!(generator.try_generate().is_ok());
+}
+
+#[test]
+fn test_try_generate_detects_rollback() {
+    let mut generator = SnowflakeGenerator::new(0, 0);
+    generator.last_time += 10_000;
+    let err = generator.try_generate().unwrap_err();
+    assert!(err.drift_millis >= 9_000);
+}
+
+#[test]
+fn test_try_generate_does_not_panic_with_future_epoch() {
+    let epoch = SystemTime::now() + Duration::from_secs(3600);
+    let mut generator = SnowflakeGenerator::with_epoch(epoch, 0, 0);
+    assert!(generator.try_generate().is_ok());
+}
+
+#[test]
+fn test_generate_rebases_after_rollback() {
+    let mut generator = SnowflakeGenerator::new(0, 0);
+    generator.last_time += 10_000;
+    let expected_time = generator.last_time;
+    let id = generator.generate();
+    assert_eq!(Snowflake::from(id).time, expected_time);
+}
+
+#[test]
+fn test_coarse_time_unit_round_trip() {
+    let layout = SnowflakeLayout::with_time_unit_millis(39, 13, 12, 10);
+    let mut generator = SnowflakeGenerator::builder()
+        .time_bits(39)
+        .node_bits(13)
+        .sequence_bits(12)
+        .time_unit_millis(10)
+        .build(3);
+    let id = generator.generate();
+    let snowflake = Snowflake::decode_with_layout(id, &layout);
+    assert_eq!(snowflake.time % 10, 0);
+    assert_eq!(id, snowflake.encode_with_layout(&layout));
+}
+
+#[test]
+#[should_panic]
+fn test_layout_time_unit_millis_must_be_nonzero() {
+    SnowflakeLayout::with_time_unit_millis(42, 10, 12, 0);
+}
+
+#[test]
+fn test_generate_fuzzy_rebases_after_rollback() {
+    let mut generator = SnowflakeGenerator::new(0, 0);
+    generator.last_time += 10_000;
+    let expected_time = generator.last_time;
+    let id = generator.generate_fuzzy();
+    assert_eq!(Snowflake::from(id).time, expected_time);
+}
+
+#[test]
+fn test_timestamp_recovers_wall_clock_time() {
+    let epoch = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut generator = SnowflakeGenerator::with_epoch(epoch, 0, 0);
+    let before = SystemTime::now();
+    let id = generator.generate();
+    let after = SystemTime::now();
+    let snowflake = Snowflake::from(id);
+    let created_at = snowflake.timestamp(epoch);
+    // `created_at` only has millisecond resolution, so it can fall up to a
+    // millisecond before `before`; give the comparison that much slack.
+    assert!(created_at >= before - Duration::from_millis(1));
+    assert!(created_at <= after);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_host_identifier_matches_proc_hostname() {
+    // Regression test: `host_identifier` must read the OS-reported hostname
+    // itself rather than only falling back to the (often-unset) `HOSTNAME`
+    // env var, so it should agree with `/proc/sys/kernel/hostname` exactly --
+    // not just differ from the fixed fallback string, which the old,
+    // env-var-only code could also satisfy by coincidence.
+    let expected = std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .unwrap()
+        .trim()
+        .to_string();
+    assert_eq!(host_identifier(), expected);
+}
+
+#[test]
+fn test_auto_node_is_deterministic() {
+    let a = SnowflakeGenerator::with_auto_node();
+    let b = SnowflakeGenerator::with_auto_node();
+    assert_eq!(a.node_id(), b.node_id());
+}
+
+#[test]
+fn test_auto_node_fits_node_bits() {
+    let generator = SnowflakeGenerator::with_auto_node();
+    assert!(generator.node_id() < SnowflakeLayout::DEFAULT.max_node());
+}
+
+#[test]
+fn test_builder_auto_node_fits_custom_node_bits() {
+    let generator = SnowflakeGenerator::builder()
+        .time_bits(41)
+        .node_bits(8)
+        .sequence_bits(14)
+        .build_with_auto_node();
+    assert!(generator.node_id() < (1 << 8));
+}
+
+#[test]
+fn test_generator_decode_uses_its_own_epoch() {
+    let epoch = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut generator = SnowflakeGenerator::with_epoch(epoch, 1, 2);
+    let id = generator.generate();
+    let snowflake = generator.decode(id);
+    assert_eq!(snowflake.node_id, (1 << 5) | 2);
+    assert_eq!(generator.epoch(), epoch);
+    let created_at = snowflake.timestamp(generator.epoch());
+    assert!(created_at >= epoch);
+}